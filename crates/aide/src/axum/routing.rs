@@ -1,23 +1,24 @@
 //! Method routing that closely mimics [`axum::routing`] while extending
 //! it with API documentation-specific features..
 
-use std::{convert::Infallible, mem};
+use std::{collections::HashSet, convert::Infallible, mem};
 
 use crate::{
     gen::GenContext,
-    openapi::{Operation, PathItem, ReferenceOr, Response, StatusCode},
+    openapi::{Header, Operation, PathItem, ReferenceOr, Response, StatusCode},
     Error,
 };
 use axum::{
     body::{Body, HttpBody},
     handler::Handler,
     response::IntoResponse,
-    routing::{self, MethodRouter, Route},
+    routing::{self, MethodFilter, MethodRouter, Route},
     BoxError,
 };
 use bytes::Bytes;
 use http::Request;
 use indexmap::IndexMap;
+use schemars::schema::{InstanceType, SchemaObject};
 use tower_layer::Layer;
 use tower_service::Service;
 
@@ -32,6 +33,11 @@ use crate::{
 #[must_use]
 pub struct ApiMethodRouter<S = (), B = Body, E = Infallible> {
     pub(crate) operations: IndexMap<&'static str, Operation>,
+    /// Methods that were explicitly routed but whose operation was hidden
+    /// (via a transform's `op.hidden()`), so a deliberately-hidden, custom
+    /// handler for one of them is never silently replaced by a synthesized
+    /// operation later (see [`Self::take_path_item`]).
+    pub(crate) hidden_operations: HashSet<&'static str>,
     pub(crate) router: MethodRouter<S, B, E>,
 }
 
@@ -45,6 +51,7 @@ impl<S, B, E> From<MethodRouter<S, B, E>> for ApiMethodRouter<S, B, E> {
     fn from(router: MethodRouter<S, B, E>) -> Self {
         Self {
             operations: IndexMap::default(),
+            hidden_operations: HashSet::default(),
             router,
         }
     }
@@ -53,8 +60,55 @@ impl<S, B, E> From<MethodRouter<S, B, E>> for ApiMethodRouter<S, B, E> {
 impl<S, B, E> ApiMethodRouter<S, B, E> {
     pub(crate) fn take_path_item(&mut self) -> PathItem {
         let mut path = PathItem::default();
+        let mut operations = mem::take(&mut self.operations);
 
-        for (method, op) in mem::take(&mut self.operations) {
+        if !operations.is_empty() {
+            in_context(|ctx| {
+                if ctx.infer_method_responses {
+                    // Include methods that are routed for real but hidden
+                    // from the docs (`self.hidden_operations`) too, or the
+                    // synthesized `Allow` would falsely claim a method isn't
+                    // supported when a real request to it would succeed.
+                    let allow = METHOD_FILTERS
+                        .iter()
+                        .filter(|&&(method, _)| {
+                            operations.contains_key(method)
+                                || self.hidden_operations.contains(method)
+                        })
+                        .map(|&(method, _)| method.to_ascii_uppercase())
+                        .collect::<Vec<_>>()
+                        .join(", ");
+
+                    // Every registered operation can fail with a `405` for
+                    // any *other* method on the same path, so the response
+                    // belongs on each of them individually -- `PathItem` has
+                    // no path-level `responses` field to attach it to once
+                    // instead.
+                    for op in operations.values_mut() {
+                        set_inferred_response(ctx, op, Some(405), allow_response(&allow));
+                    }
+
+                    // The synthesized `OPTIONS` operation is skipped
+                    // entirely if the caller already registered their own
+                    // `options`/`options_with` handler, including one
+                    // deliberately hidden from the docs.
+                    if !operations.contains_key("options")
+                        && !self.hidden_operations.contains("options")
+                    {
+                        let mut options = Operation::default();
+                        set_inferred_response(
+                            ctx,
+                            &mut options,
+                            Some(200),
+                            allow_response(&allow),
+                        );
+                        operations.insert("options", options);
+                    }
+                }
+            });
+        }
+
+        for (method, op) in operations {
             match method {
                 "delete" => path.delete = Some(op),
                 "get" => path.get = Some(op),
@@ -64,12 +118,76 @@ impl<S, B, E> ApiMethodRouter<S, B, E> {
                 "post" => path.post = Some(op),
                 "put" => path.put = Some(op),
                 "trace" => path.trace = Some(op),
-                _ => unreachable!(),
+                _ => {
+                    // Only the methods in `METHOD_FILTERS` are ever inserted
+                    // into `self.operations`, so this is unreachable in
+                    // practice; handled gracefully rather than panicking in
+                    // case that invariant ever changes.
+                }
             }
         }
 
         path
     }
+
+    /// Insert a clone of `operation` for every method bit set in `filter`.
+    fn insert_operation_for_filter(&mut self, filter: MethodFilter, operation: Operation) {
+        for (method, bit) in METHOD_FILTERS {
+            if filter.contains(*bit) {
+                self.operations.insert(method, operation.clone());
+            }
+        }
+    }
+
+    /// Record every method bit set in `filter` as hidden, so none of them
+    /// get a synthesized operation later (see [`Self::take_path_item`]).
+    fn mark_hidden_for_filter(&mut self, filter: MethodFilter) {
+        for (method, bit) in METHOD_FILTERS {
+            if filter.contains(*bit) {
+                self.hidden_operations.insert(method);
+            }
+        }
+    }
+}
+
+/// The method router keys, paired with the [`MethodFilter`] bit they
+/// correspond to.
+const METHOD_FILTERS: &[(&str, MethodFilter)] = &[
+    ("delete", MethodFilter::DELETE),
+    ("get", MethodFilter::GET),
+    ("head", MethodFilter::HEAD),
+    ("options", MethodFilter::OPTIONS),
+    ("patch", MethodFilter::PATCH),
+    ("post", MethodFilter::POST),
+    ("put", MethodFilter::PUT),
+    ("trace", MethodFilter::TRACE),
+];
+
+/// Build a [`Response`] documenting the `Allow` header that axum's
+/// [`MethodRouter`] sets on `405 Method Not Allowed` responses (and on its
+/// generated `OPTIONS` handler), listing the given methods.
+fn allow_response(allow: &str) -> Response {
+    let mut schema = SchemaObject {
+        instance_type: Some(InstanceType::String.into()),
+        ..Default::default()
+    };
+    schema.const_value = Some(allow.into());
+
+    let mut headers = IndexMap::new();
+    headers.insert(
+        "Allow".to_string(),
+        ReferenceOr::Item(Header {
+            description: Some(format!("The allowed methods are `{allow}`.")),
+            schema: Some(schema.into()),
+            ..Default::default()
+        }),
+    );
+
+    Response {
+        description: format!("The allowed methods for this path are `{allow}`."),
+        headers,
+        ..Default::default()
+    }
 }
 
 macro_rules! method_router_chain_method {
@@ -132,6 +250,8 @@ macro_rules! method_router_chain_method {
 
             if !t.hidden {
                 self.operations.insert(stringify!($name), operation);
+            } else {
+                self.hidden_operations.insert(stringify!($name));
             }
 
             self.router = self.router.$name(handler);
@@ -218,6 +338,102 @@ macro_rules! method_router_top_level {
 
             if !t.hidden {
                 router.operations.insert(stringify!($name), operation);
+            } else {
+                router.hidden_operations.insert(stringify!($name));
+            }
+
+            router
+        }
+    };
+}
+
+macro_rules! method_router_chain_method_service {
+    ($name:ident, $name_with:ident) => {
+        #[doc = concat!("Route `", stringify!($name) ,"` requests to the given service. See [`axum::routing::MethodRouter::", stringify!($name) , "`] for more details.")]
+        ///
+        /// Since a [`tower::Service`] doesn't carry enough information to
+        #[doc = concat!("infer any documentation, no [`Operation`] is added for this route; use [`", stringify!($name_with), "`](Self::", stringify!($name_with), ") to describe it manually.")]
+        pub fn $name<T>(mut self, service: T) -> Self
+        where
+            T: Service<Request<B>, Error = E> + Clone + Send + 'static,
+            T::Response: IntoResponse + 'static,
+            T::Future: Send + 'static,
+        {
+            self.router = self.router.$name(service);
+            self
+        }
+
+        #[doc = concat!("Route `", stringify!($name) ,"` requests to the given service. See [`axum::routing::MethodRouter::", stringify!($name) , "`] for more details.")]
+        ///
+        /// This method requires a transform function to describe the
+        /// operation, as it cannot be inferred from a [`tower::Service`],
+        /// see [`crate::axum`] for more details.
+        pub fn $name_with<T, F>(mut self, service: T, transform: F) -> Self
+        where
+            T: Service<Request<B>, Error = E> + Clone + Send + 'static,
+            T::Response: IntoResponse + 'static,
+            T::Future: Send + 'static,
+            F: FnOnce(TransformOperation) -> TransformOperation,
+        {
+            let mut operation = Operation::default();
+            let t = transform(TransformOperation::new(&mut operation));
+
+            if !t.hidden {
+                self.operations.insert(stringify!($name), operation);
+            } else {
+                self.hidden_operations.insert(stringify!($name));
+            }
+
+            self.router = self.router.$name(service);
+            self
+        }
+    };
+}
+
+macro_rules! method_router_top_level_service {
+    ($name:ident, $name_with:ident) => {
+        #[doc = concat!("Route `", stringify!($name) ,"` requests to the given service. See [`axum::routing::", stringify!($name) , "`] for more details.")]
+        ///
+        /// Since a [`tower::Service`] doesn't carry enough information to
+        #[doc = concat!("infer any documentation, no [`Operation`] is added for this route; use [`", stringify!($name_with), "`] to describe it manually.")]
+        #[tracing::instrument(skip_all)]
+        pub fn $name<T, B, S>(service: T) -> ApiMethodRouter<S, B, T::Error>
+        where
+            T: Service<Request<B>> + Clone + Send + 'static,
+            T::Response: IntoResponse + 'static,
+            T::Future: Send + 'static,
+            B: HttpBody + Send + Sync + 'static,
+            S: Clone + Send + Sync + 'static,
+        {
+            ApiMethodRouter::from(routing::$name(service))
+        }
+
+        #[doc = concat!("Route `", stringify!($name) ,"` requests to the given service. See [`axum::routing::", stringify!($name) , "`] for more details.")]
+        ///
+        /// This function requires a transform function to describe the
+        /// operation, as it cannot be inferred from a [`tower::Service`],
+        /// see [`crate::axum`] for more details.
+        #[tracing::instrument(skip_all)]
+        pub fn $name_with<T, B, S, F>(
+            service: T,
+            transform: F,
+        ) -> ApiMethodRouter<S, B, T::Error>
+        where
+            T: Service<Request<B>> + Clone + Send + 'static,
+            T::Response: IntoResponse + 'static,
+            T::Future: Send + 'static,
+            B: HttpBody + Send + Sync + 'static,
+            S: Clone + Send + Sync + 'static,
+            F: FnOnce(TransformOperation) -> TransformOperation,
+        {
+            let mut router = ApiMethodRouter::from(routing::$name(service));
+            let mut operation = Operation::default();
+            let t = transform(TransformOperation::new(&mut operation));
+
+            if !t.hidden {
+                router.operations.insert(stringify!($name), operation);
+            } else {
+                router.hidden_operations.insert(stringify!($name));
             }
 
             router
@@ -271,6 +487,75 @@ where
     method_router_chain_method!(put, put_with);
     method_router_chain_method!(trace, trace_with);
 
+    /// Route requests matching `filter` to the given handler, registering
+    /// the resulting [`Operation`] under every method the filter contains.
+    /// See [`axum::routing::MethodRouter::on`] for more details.
+    pub fn on<H, I, O, T>(mut self, filter: MethodFilter, handler: H) -> Self
+    where
+        H: Handler<T, S, B> + OperationHandler<I, O>,
+        I: OperationInput,
+        O: OperationOutput,
+        B: Send + 'static,
+        T: 'static,
+    {
+        let mut operation = Operation::default();
+        in_context(|ctx| {
+            I::operation_input(ctx, &mut operation);
+
+            for (code, res) in O::inferred_responses(ctx, &mut operation) {
+                set_inferred_response(ctx, &mut operation, code, res);
+            }
+        });
+        self.insert_operation_for_filter(filter, operation);
+        self.router = self.router.on(filter, handler);
+        self
+    }
+
+    /// Route requests matching `filter` to the given handler, registering
+    /// the resulting [`Operation`] under every method the filter contains.
+    ///
+    /// This method additionally accepts a transform function,
+    /// see [`crate::axum`] for more details.
+    pub fn on_with<H, I, O, T, F>(mut self, filter: MethodFilter, handler: H, transform: F) -> Self
+    where
+        H: Handler<T, S, B> + OperationHandler<I, O>,
+        I: OperationInput,
+        O: OperationOutput,
+        B: Send + 'static,
+        T: 'static,
+        F: FnOnce(TransformOperation) -> TransformOperation,
+    {
+        let mut operation = Operation::default();
+        in_context(|ctx| {
+            I::operation_input(ctx, &mut operation);
+
+            if ctx.infer_responses {
+                for (code, res) in O::inferred_responses(ctx, &mut operation) {
+                    set_inferred_response(ctx, &mut operation, code, res);
+                }
+
+                // On conflict, input early responses potentially overwrite
+                // output inferred responses on purpose, as they
+                // are stronger in a sense that the request won't
+                // even reach the handler body.
+                for (code, res) in I::inferred_early_responses(ctx, &mut operation) {
+                    set_inferred_response(ctx, &mut operation, code, res);
+                }
+            }
+        });
+
+        let t = transform(TransformOperation::new(&mut operation));
+
+        if !t.hidden {
+            self.insert_operation_for_filter(filter, operation);
+        } else {
+            self.mark_hidden_for_filter(filter);
+        }
+
+        self.router = self.router.on(filter, handler);
+        self
+    }
+
     /// This method wraps a layer around the [`ApiMethodRouter`]
     /// For further information see [`axum::routing::method_routing::MethodRouter::layer`]
     pub fn layer<L, NewReqBody, NewResBody, NewError>(
@@ -296,6 +581,7 @@ where
         ApiMethodRouter {
             router: self.router.layer(layer),
             operations: self.operations,
+            hidden_operations: self.hidden_operations,
         }
     }
 
@@ -311,8 +597,123 @@ where
         ApiMethodRouter {
             router: self.router.route_layer(layer),
             operations: self.operations,
+            hidden_operations: self.hidden_operations,
         }
     }
+
+    /// Like [`Self::layer`], but additionally documents the effect `layer`
+    /// has on every operation currently registered on this router.
+    ///
+    /// `transform` is applied to every operation, and every response in
+    /// `documented` is merged in too, through the same machinery as
+    /// [`Self::get_with`] and friends (so
+    /// [`Error::InferredResponseConflict`] still applies). `documented` is
+    /// a plain argument rather than a bound on `L` because most layers
+    /// worth documenting this way (an auth layer, a rate limiter, `tower_http`'s
+    /// `CompressionLayer`, ...) live in a crate the caller doesn't own, and
+    /// Rust's orphan rule would forbid implementing any such trait for
+    /// them. If `L` does implement [`DocumentedLayer`], pass
+    /// `in_context(|ctx| layer.documented_responses(ctx))` as `documented`.
+    pub fn layer_with<L, NewReqBody, NewResBody, NewError, F>(
+        mut self,
+        layer: L,
+        documented: impl IntoIterator<Item = (Option<u16>, Response)>,
+        transform: F,
+    ) -> ApiMethodRouter<S, NewReqBody, NewError>
+    where
+        L: Layer<Route<B, Infallible>> + Clone + Send + 'static,
+        L::Service: Service<
+                Request<NewReqBody>,
+                Response = http::response::Response<NewResBody>,
+                Error = NewError,
+            > + Clone
+            + Send
+            + 'static,
+        <L::Service as Service<Request<NewReqBody>>>::Future: Send + 'static,
+        NewResBody: 'static,
+        NewReqBody: HttpBody + 'static,
+        NewError: 'static,
+        NewResBody: HttpBody<Data = Bytes> + Send + 'static,
+        NewResBody::Error: Into<BoxError>,
+        F: Fn(&mut TransformOperation),
+    {
+        let documented: Vec<_> = documented.into_iter().collect();
+
+        in_context(|ctx| {
+            for op in self.operations.values_mut() {
+                transform(&mut TransformOperation::new(op));
+
+                for (code, res) in documented.iter().cloned() {
+                    set_inferred_response(ctx, op, code, res);
+                }
+            }
+        });
+
+        ApiMethodRouter {
+            router: self.router.layer(layer),
+            operations: self.operations,
+            hidden_operations: self.hidden_operations,
+        }
+    }
+
+    /// Like [`Self::route_layer`], but additionally documents the effect
+    /// `layer` has on every operation currently registered on this router.
+    /// See [`Self::layer_with`] for details on `transform` and `documented`.
+    pub fn route_layer_with<L, F>(
+        mut self,
+        layer: L,
+        documented: impl IntoIterator<Item = (Option<u16>, Response)>,
+        transform: F,
+    ) -> Self
+    where
+        L: Layer<Route<B, Infallible>> + Clone + Send + 'static,
+        L::Service: Service<Request<B>, Error = Infallible> + Clone + Send + 'static,
+        <L::Service as Service<Request<B>>>::Response: IntoResponse + 'static,
+        <L::Service as Service<Request<B>>>::Future: Send + 'static,
+        F: Fn(&mut TransformOperation),
+    {
+        let documented: Vec<_> = documented.into_iter().collect();
+
+        in_context(|ctx| {
+            for op in self.operations.values_mut() {
+                transform(&mut TransformOperation::new(op));
+
+                for (code, res) in documented.iter().cloned() {
+                    set_inferred_response(ctx, op, code, res);
+                }
+            }
+        });
+
+        self.router = self.router.route_layer(layer);
+        self
+    }
+}
+
+/// A [`tower_layer::Layer`] that can describe, through additional
+/// responses, the effect it has on every operation it wraps.
+///
+/// Implement this for cross-cutting middleware you own (an auth layer that
+/// may reject a request before it reaches the handler, a rate limiter that
+/// can answer with `429`, ...) so the documentation only has to be written
+/// once, on the layer, rather than repeated on every handler it wraps.
+/// Because of the orphan rule this can't be implemented for layers from
+/// another crate (e.g. `tower_http`'s `CompressionLayer`); for those,
+/// build the `(Option<u16>, Response)` list by hand instead. Either way,
+/// pass the result to [`ApiMethodRouter::layer_with`] /
+/// [`ApiMethodRouter::route_layer_with`] as their `documented` argument.
+///
+/// Only early responses are in scope for now -- there's no hook for a
+/// layer to contribute parameters or a security requirement, so document
+/// those by hand with `transform` where [`ApiMethodRouter::layer_with`] /
+/// [`ApiMethodRouter::route_layer_with`] are called instead.
+pub trait DocumentedLayer {
+    /// The early responses this layer's behavior adds to every operation it
+    /// wraps, e.g. a `401` for an auth layer, or a `429` for a rate
+    /// limiter.
+    fn documented_responses(&self, ctx: &mut GenContext) -> Vec<(Option<u16>, Response)> {
+        let _ = ctx;
+        Vec::new()
+    }
 }
 
 impl<S, B, E> ApiMethodRouter<S, B, E>
@@ -324,6 +725,7 @@ where
     pub fn new() -> Self {
         Self {
             operations: IndexMap::default(),
+            hidden_operations: HashSet::default(),
             router: MethodRouter::<S, B, E>::new(),
         }
     }
@@ -332,18 +734,117 @@ where
         let router = self.router.with_state(state);
         ApiMethodRouter::<S2, B, E> {
             operations: self.operations,
+            hidden_operations: self.hidden_operations,
             router,
         }
     }
 
     /// See [`axum::routing::MethodRouter::merge`] for more information.
+    ///
+    /// If both routers already have an operation for the same method, an
+    /// [`Error::OperationMergeConflict`] is reported through the current
+    /// [`GenContext`], mirroring how axum's router treats overlapping route
+    /// registrations as a hard error instead of silently overwriting them.
+    /// This also catches a method that's only in `hidden_operations` on
+    /// either side -- it's hidden from the docs, not unrouted, so merging it
+    /// with the same method on the other router would still panic below.
+    /// Methods routed without any operation being recorded (through
+    /// [`Self::get_service`] and friends, or a plain [`MethodRouter`]
+    /// converted with `From`) are not checked, so an overlap between those
+    /// still reaches axum's merge and panics there.
+    ///
+    /// axum's own [`MethodRouter::merge`] panics on an overlapping method
+    /// registration rather than returning a recoverable error, so the
+    /// underlying routers are only merged once `other` is known to be
+    /// conflict-free -- doing so unconditionally would crash the process via
+    /// that panic before the caller ever gets a chance to read back the
+    /// error just queued on [`GenContext`]. `self.operations` is all-or-
+    /// nothing along with the router merge: on conflict, *none* of `other`'s
+    /// operations are adopted, not just the conflicting one, since the
+    /// router merge they'd be documenting never happens either; partially
+    /// adopting them would make the generated spec claim routes that were
+    /// never actually merged into `self.router`.
     pub fn merge<M>(mut self, other: M) -> Self
     where
         M: Into<ApiMethodRouter<S, B, E>>,
     {
         let other = other.into();
+
+        let conflict = in_context(|ctx| {
+            let mut conflict = false;
+            for &(method, _) in METHOD_FILTERS {
+                let self_has_method = self.operations.contains_key(method)
+                    || self.hidden_operations.contains(method);
+                let other_has_method = other.operations.contains_key(method)
+                    || other.hidden_operations.contains(method);
+
+                if self_has_method && other_has_method {
+                    conflict = true;
+                    ctx.error(Error::OperationMergeConflict { method });
+                }
+            }
+            conflict
+        });
+
+        if conflict {
+            return self;
+        }
+
         self.operations.extend(other.operations);
+        self.hidden_operations.extend(other.hidden_operations);
         self.router = self.router.merge(other.router);
+
+        self
+    }
+
+    method_router_chain_method_service!(delete_service, delete_service_with);
+    method_router_chain_method_service!(get_service, get_service_with);
+    method_router_chain_method_service!(head_service, head_service_with);
+    method_router_chain_method_service!(options_service, options_service_with);
+    method_router_chain_method_service!(patch_service, patch_service_with);
+    method_router_chain_method_service!(post_service, post_service_with);
+    method_router_chain_method_service!(put_service, put_service_with);
+    method_router_chain_method_service!(trace_service, trace_service_with);
+
+    /// Route requests matching `filter` to the given service.
+    /// See [`axum::routing::MethodRouter::on_service`] for more details.
+    ///
+    /// Since a [`tower::Service`] doesn't carry enough information to infer
+    /// any documentation, no [`Operation`] is added for this route; use
+    /// [`on_service_with`](Self::on_service_with) to describe it manually.
+    pub fn on_service<T>(mut self, filter: MethodFilter, service: T) -> Self
+    where
+        T: Service<Request<B>, Error = E> + Clone + Send + 'static,
+        T::Response: IntoResponse + 'static,
+        T::Future: Send + 'static,
+    {
+        self.router = self.router.on_service(filter, service);
+        self
+    }
+
+    /// Route requests matching `filter` to the given service, registering
+    /// the resulting [`Operation`] under every method the filter contains.
+    ///
+    /// This method requires a transform function to describe the operation,
+    /// as it cannot be inferred from a [`tower::Service`], see
+    /// [`crate::axum`] for more details.
+    pub fn on_service_with<T, F>(mut self, filter: MethodFilter, service: T, transform: F) -> Self
+    where
+        T: Service<Request<B>, Error = E> + Clone + Send + 'static,
+        T::Response: IntoResponse + 'static,
+        T::Future: Send + 'static,
+        F: FnOnce(TransformOperation) -> TransformOperation,
+    {
+        let mut operation = Operation::default();
+        let t = transform(TransformOperation::new(&mut operation));
+
+        if !t.hidden {
+            self.insert_operation_for_filter(filter, operation);
+        } else {
+            self.mark_hidden_for_filter(filter);
+        }
+
+        self.router = self.router.on_service(filter, service);
         self
     }
 }
@@ -366,3 +867,437 @@ method_router_top_level!(patch, patch_with);
 method_router_top_level!(post, post_with);
 method_router_top_level!(put, put_with);
 method_router_top_level!(trace, trace_with);
+
+/// Route requests matching `filter` to the given handler, registering the
+/// resulting [`Operation`] under every method the filter contains. See
+/// [`axum::routing::on`] for more details.
+#[tracing::instrument(skip_all)]
+pub fn on<H, I, O, T, B, S>(filter: MethodFilter, handler: H) -> ApiMethodRouter<S, B, Infallible>
+where
+    H: Handler<T, S, B> + OperationHandler<I, O>,
+    I: OperationInput,
+    O: OperationOutput,
+    B: HttpBody + Send + Sync + 'static,
+    S: Clone + Send + Sync + 'static,
+    T: 'static,
+{
+    let mut router = ApiMethodRouter::from(routing::on(filter, handler));
+    let mut operation = Operation::default();
+    in_context(|ctx| {
+        I::operation_input(ctx, &mut operation);
+
+        for (code, res) in O::inferred_responses(ctx, &mut operation) {
+            set_inferred_response(ctx, &mut operation, code, res);
+        }
+
+        // On conflict, input early responses potentially overwrite
+        // output inferred responses on purpose, as they
+        // are stronger in a sense that the request won't
+        // even reach the handler body.
+        for (code, res) in I::inferred_early_responses(ctx, &mut operation) {
+            set_inferred_response(ctx, &mut operation, code, res);
+        }
+    });
+
+    router.insert_operation_for_filter(filter, operation);
+
+    router
+}
+
+/// Route requests matching `filter` to the given handler, registering the
+/// resulting [`Operation`] under every method the filter contains.
+///
+/// This method additionally accepts a transform function,
+/// see [`crate::axum`] for more details.
+#[tracing::instrument(skip_all)]
+pub fn on_with<H, I, O, T, B, S, F>(
+    filter: MethodFilter,
+    handler: H,
+    transform: F,
+) -> ApiMethodRouter<S, B, Infallible>
+where
+    H: Handler<T, S, B> + OperationHandler<I, O>,
+    I: OperationInput,
+    O: OperationOutput,
+    B: axum::body::HttpBody + Send + Sync + 'static,
+    S: Clone + Send + Sync + 'static,
+    T: 'static,
+    F: FnOnce(TransformOperation) -> TransformOperation,
+{
+    let mut router = ApiMethodRouter::from(routing::on(filter, handler));
+    let mut operation = Operation::default();
+    in_context(|ctx| {
+        I::operation_input(ctx, &mut operation);
+
+        if ctx.infer_responses {
+            for (code, res) in O::inferred_responses(ctx, &mut operation) {
+                set_inferred_response(ctx, &mut operation, code, res);
+            }
+
+            // On conflict, input early responses potentially overwrite
+            // output inferred responses on purpose, as they
+            // are stronger in a sense that the request won't
+            // even reach the handler body.
+            for (code, res) in I::inferred_early_responses(ctx, &mut operation) {
+                set_inferred_response(ctx, &mut operation, code, res);
+            }
+        }
+    });
+
+    let t = transform(TransformOperation::new(&mut operation));
+
+    if !t.hidden {
+        router.insert_operation_for_filter(filter, operation);
+    } else {
+        router.mark_hidden_for_filter(filter);
+    }
+
+    router
+}
+
+method_router_top_level_service!(delete_service, delete_service_with);
+method_router_top_level_service!(get_service, get_service_with);
+method_router_top_level_service!(head_service, head_service_with);
+method_router_top_level_service!(options_service, options_service_with);
+method_router_top_level_service!(patch_service, patch_service_with);
+method_router_top_level_service!(post_service, post_service_with);
+method_router_top_level_service!(put_service, put_service_with);
+method_router_top_level_service!(trace_service, trace_service_with);
+
+/// Route requests matching `filter` to the given service. See
+/// [`axum::routing::on_service`] for more details.
+///
+/// Since a [`tower::Service`] doesn't carry enough information to infer any
+/// documentation, no [`Operation`] is added for this route; use
+/// [`on_service_with`] to describe it manually.
+#[tracing::instrument(skip_all)]
+pub fn on_service<T, B, S>(
+    filter: MethodFilter,
+    service: T,
+) -> ApiMethodRouter<S, B, T::Error>
+where
+    T: Service<Request<B>> + Clone + Send + 'static,
+    T::Response: IntoResponse + 'static,
+    T::Future: Send + 'static,
+    B: HttpBody + Send + Sync + 'static,
+    S: Clone + Send + Sync + 'static,
+{
+    ApiMethodRouter::from(routing::on_service(filter, service))
+}
+
+/// Route requests matching `filter` to the given service, registering the
+/// resulting [`Operation`] under every method the filter contains.
+///
+/// This function requires a transform function to describe the operation,
+/// as it cannot be inferred from a [`tower::Service`], see [`crate::axum`]
+/// for more details.
+#[tracing::instrument(skip_all)]
+pub fn on_service_with<T, B, S, F>(
+    filter: MethodFilter,
+    service: T,
+    transform: F,
+) -> ApiMethodRouter<S, B, T::Error>
+where
+    T: Service<Request<B>> + Clone + Send + 'static,
+    T::Response: IntoResponse + 'static,
+    T::Future: Send + 'static,
+    B: HttpBody + Send + Sync + 'static,
+    S: Clone + Send + Sync + 'static,
+    F: FnOnce(TransformOperation) -> TransformOperation,
+{
+    let mut router = ApiMethodRouter::from(routing::on_service(filter, service));
+    let mut operation = Operation::default();
+    let t = transform(TransformOperation::new(&mut operation));
+
+    if !t.hidden {
+        router.insert_operation_for_filter(filter, operation);
+    } else {
+        router.mark_hidden_for_filter(filter);
+    }
+
+    router
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn merge_reports_conflict_and_discards_the_other_routers_operations() {
+        let mut a = ApiMethodRouter::<(), Body, Infallible>::new();
+        a.operations.insert("get", Operation::default());
+
+        let mut b = ApiMethodRouter::<(), Body, Infallible>::new();
+        b.operations.insert("get", Operation::default());
+        b.operations.insert("post", Operation::default());
+
+        let errors_before = in_context(|ctx| ctx.errors.len());
+        let merged = a.merge(b);
+        let errors_after = in_context(|ctx| ctx.errors.len());
+
+        assert_eq!(errors_after, errors_before + 1);
+        assert!(in_context(|ctx| matches!(
+            ctx.errors.last(),
+            Some(Error::OperationMergeConflict { method: "get" })
+        )));
+
+        // `a`'s `get` operation is kept, but `other`'s router was never
+        // merged in either, so `other`'s non-conflicting `post` operation
+        // must be discarded along with it -- otherwise the spec would claim
+        // a route that doesn't actually exist.
+        assert!(merged.operations.contains_key("get"));
+        assert!(!merged.operations.contains_key("post"));
+    }
+
+    #[test]
+    fn merge_reports_conflict_between_a_hidden_and_a_visible_operation() {
+        let mut a = ApiMethodRouter::<(), Body, Infallible>::new();
+        a.operations.insert("get", Operation::default());
+
+        // `b`'s `get` is routed for real, just hidden from the docs -- it
+        // must still collide with `a`'s visible `get`, or merging the two
+        // routers below would hit axum's overlapping-route panic.
+        let mut b = ApiMethodRouter::<(), Body, Infallible>::new();
+        b.hidden_operations.insert("get");
+
+        let errors_before = in_context(|ctx| ctx.errors.len());
+        let merged = a.merge(b);
+        let errors_after = in_context(|ctx| ctx.errors.len());
+
+        assert_eq!(errors_after, errors_before + 1);
+        assert!(in_context(|ctx| matches!(
+            ctx.errors.last(),
+            Some(Error::OperationMergeConflict { method: "get" })
+        )));
+        assert!(!merged.hidden_operations.contains("get"));
+    }
+
+    #[tokio::test]
+    async fn merge_conflict_keeps_the_spec_and_the_live_router_in_sync() {
+        use tower::ServiceExt;
+
+        async fn a_handler() -> &'static str {
+            "a"
+        }
+        async fn b_handler() -> &'static str {
+            "b"
+        }
+
+        let a = get_with(a_handler, |op| op);
+        let b = get_with(b_handler, |op| op).post_with(b_handler, |op| op);
+
+        let merged = a.merge(b);
+        assert!(!merged.operations.contains_key("post"));
+
+        let router = axum::Router::new().route("/", merged.into());
+
+        let get_response = router
+            .clone()
+            .oneshot(
+                http::Request::builder()
+                    .method("GET")
+                    .uri("/")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(get_response.status(), http::StatusCode::OK);
+
+        // `b`'s `post` handler was discarded along with its operation, so a
+        // real request can't reach it either -- the documented `post`
+        // absence and the router's actual behavior agree.
+        let post_response = router
+            .oneshot(
+                http::Request::builder()
+                    .method("POST")
+                    .uri("/")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(post_response.status(), http::StatusCode::METHOD_NOT_ALLOWED);
+    }
+
+    fn has_response(op: &Operation, status: u16) -> bool {
+        op.responses
+            .as_ref()
+            .is_some_and(|responses| responses.responses.contains_key(&StatusCode::Code(status)))
+    }
+
+    fn response(op: &Operation, status: u16) -> &Response {
+        match op
+            .responses
+            .as_ref()
+            .unwrap()
+            .responses
+            .get(&StatusCode::Code(status))
+            .unwrap()
+        {
+            ReferenceOr::Item(response) => response,
+            ReferenceOr::Reference { .. } => panic!("expected an inline response"),
+        }
+    }
+
+    #[test]
+    fn take_path_item_synthesizes_options_for_registered_methods() {
+        let mut router = ApiMethodRouter::<(), Body, Infallible>::new();
+        router.operations.insert("get", Operation::default());
+        router.operations.insert("post", Operation::default());
+
+        in_context(|ctx| ctx.infer_method_responses = true);
+        let path = router.take_path_item();
+
+        assert!(path.get.is_some());
+        assert!(path.post.is_some());
+        assert!(path.options.is_some());
+    }
+
+    #[test]
+    fn take_path_item_synthesizes_405_on_every_registered_operation() {
+        let mut router = ApiMethodRouter::<(), Body, Infallible>::new();
+        router.operations.insert("get", Operation::default());
+        router.operations.insert("post", Operation::default());
+
+        in_context(|ctx| ctx.infer_method_responses = true);
+        let path = router.take_path_item();
+
+        assert!(has_response(path.get.as_ref().unwrap(), 405));
+        assert!(has_response(path.post.as_ref().unwrap(), 405));
+        assert!(has_response(path.options.as_ref().unwrap(), 200));
+    }
+
+    #[test]
+    fn take_path_item_includes_hidden_methods_in_the_allow_list() {
+        let mut router = ApiMethodRouter::<(), Body, Infallible>::new();
+        router.operations.insert("get", Operation::default());
+        // `put` is still routed for real, just hidden from the docs.
+        router.hidden_operations.insert("put");
+
+        in_context(|ctx| ctx.infer_method_responses = true);
+        let path = router.take_path_item();
+
+        assert!(path.put.is_none());
+
+        // The synthesized `405`/`OPTIONS` must still advertise `PUT` as
+        // allowed, or the docs would claim a real request to it fails.
+        assert!(response(path.get.as_ref().unwrap(), 405)
+            .description
+            .contains("PUT"));
+        assert!(response(path.options.as_ref().unwrap(), 200)
+            .description
+            .contains("PUT"));
+    }
+
+    #[test]
+    fn take_path_item_does_not_synthesize_options_over_a_hidden_handler() {
+        let mut router = ApiMethodRouter::<(), Body, Infallible>::new();
+        router.operations.insert("get", Operation::default());
+        router.hidden_operations.insert("options");
+
+        in_context(|ctx| ctx.infer_method_responses = true);
+        let path = router.take_path_item();
+
+        // A real `options` handler is still routed (it's just not in
+        // `self.operations` because it was hidden), so the synthesized one
+        // must not silently take its place in the docs.
+        assert!(path.options.is_none());
+    }
+
+    #[test]
+    fn take_path_item_skips_synthesis_when_disabled() {
+        let mut router = ApiMethodRouter::<(), Body, Infallible>::new();
+        router.operations.insert("get", Operation::default());
+
+        in_context(|ctx| ctx.infer_method_responses = false);
+        let path = router.take_path_item();
+
+        assert!(path.options.is_none());
+    }
+
+    async fn test_handler() -> &'static str {
+        "ok"
+    }
+
+    #[test]
+    fn on_with_registers_the_same_operation_under_every_filtered_method() {
+        let router = ApiMethodRouter::<(), Body, Infallible>::new().on_with(
+            MethodFilter::GET | MethodFilter::POST,
+            test_handler,
+            |op| op,
+        );
+
+        assert_eq!(router.operations.len(), 2);
+        assert!(router.operations.contains_key("get"));
+        assert!(router.operations.contains_key("post"));
+        assert!(!router.operations.contains_key("put"));
+    }
+
+    fn test_service(
+        _req: Request<Body>,
+    ) -> std::future::Ready<Result<(), Infallible>> {
+        std::future::ready(Ok(()))
+    }
+
+    #[test]
+    fn service_adds_no_operation() {
+        let router = ApiMethodRouter::<(), Body, Infallible>::new()
+            .get_service(tower::service_fn(test_service));
+
+        assert!(router.operations.is_empty());
+    }
+
+    #[test]
+    fn service_with_respects_hidden_transform() {
+        let router = ApiMethodRouter::<(), Body, Infallible>::new()
+            .get_service_with(tower::service_fn(test_service), |op| op.hidden());
+
+        assert!(!router.operations.contains_key("get"));
+        assert!(router.hidden_operations.contains("get"));
+    }
+
+    fn auth_response() -> Response {
+        Response {
+            description: "Missing or invalid Authorization header.".to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn route_layer_with_documents_every_operation_and_reports_response_conflicts() {
+        use tower::layer::util::Identity;
+
+        let router = ApiMethodRouter::<(), Body, Infallible>::new()
+            .get(test_handler)
+            .post(test_handler)
+            .route_layer_with(Identity::new(), [(Some(401), auth_response())], |_op| {});
+
+        assert!(has_response(&router.operations["get"], 401));
+        assert!(has_response(&router.operations["post"], 401));
+
+        let errors_before = in_context(|ctx| ctx.errors.len());
+        // The same status code documented twice, by two different layers,
+        // is as much a conflict as two handlers claiming it.
+        let _router =
+            router.route_layer_with(Identity::new(), [(Some(401), auth_response())], |_op| {});
+        let errors_after = in_context(|ctx| ctx.errors.len());
+
+        assert_eq!(errors_after, errors_before + 2);
+        assert!(in_context(|ctx| matches!(
+            ctx.errors.last(),
+            Some(Error::InferredResponseConflict(401))
+        )));
+    }
+
+    #[test]
+    fn layer_with_documents_every_operation() {
+        use tower::layer::util::Identity;
+
+        let router = ApiMethodRouter::<(), Body, Infallible>::new()
+            .get(test_handler)
+            .layer_with(Identity::new(), [(Some(401), auth_response())], |_op| {});
+
+        assert!(has_response(&router.operations["get"], 401));
+    }
+}
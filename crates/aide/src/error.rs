@@ -0,0 +1,44 @@
+//! Error types returned while generating an OpenAPI document.
+
+use std::fmt;
+
+/// Errors that can occur while generating an OpenAPI document.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum Error {
+    /// An inferred response for this status code was already set on the
+    /// operation.
+    InferredResponseConflict(u16),
+
+    /// An inferred default response was already set on the operation.
+    InferredDefaultResponseConflict,
+
+    /// [`ApiMethodRouter::merge`](crate::axum::routing::ApiMethodRouter::merge)
+    /// was called on two routers that both already had an operation
+    /// registered for the same method.
+    OperationMergeConflict {
+        /// The method both routers had an operation registered for.
+        method: &'static str,
+    },
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::InferredResponseConflict(status) => {
+                write!(f, "an inferred response for status code {status} already exists")
+            }
+            Error::InferredDefaultResponseConflict => {
+                write!(f, "an inferred default response already exists")
+            }
+            Error::OperationMergeConflict { method } => {
+                write!(
+                    f,
+                    "cannot merge: both routers already have an operation for `{method}`"
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for Error {}
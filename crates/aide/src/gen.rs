@@ -0,0 +1,42 @@
+//! Context carried around while generating an OpenAPI document.
+
+use std::cell::RefCell;
+
+use crate::Error;
+
+/// Context passed to the various `operation_input`/`inferred_responses`/...
+/// hooks while generating an OpenAPI document. Collects non-fatal errors
+/// and carries flags that influence how generation behaves.
+#[derive(Debug, Default)]
+pub struct GenContext {
+    /// Whether to infer responses from a handler's input/output types.
+    pub infer_responses: bool,
+
+    /// Whether [`ApiMethodRouter::take_path_item`](crate::axum::routing::ApiMethodRouter::take_path_item)
+    /// should synthesize a `405` response on every registered operation and
+    /// an `OPTIONS` operation, both documenting the `Allow` header from the
+    /// router's set of registered methods.
+    pub infer_method_responses: bool,
+
+    /// Non-fatal errors collected so far.
+    pub errors: Vec<Error>,
+}
+
+impl GenContext {
+    /// Record a non-fatal error encountered during generation.
+    pub fn error(&mut self, error: Error) {
+        self.errors.push(error);
+    }
+}
+
+thread_local! {
+    static CONTEXT: RefCell<GenContext> = RefCell::new(GenContext::default());
+}
+
+/// Run `f` with mutable access to the current [`GenContext`].
+pub fn in_context<F, R>(f: F) -> R
+where
+    F: FnOnce(&mut GenContext) -> R,
+{
+    CONTEXT.with(|ctx| f(&mut ctx.borrow_mut()))
+}